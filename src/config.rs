@@ -0,0 +1,24 @@
+use std::env;
+
+// Application configuration loaded from the environment
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub jwt_secret: String,
+    pub jwt_expires_in: String,
+    pub jwt_maxage: i64,
+}
+
+impl Config {
+    // Load configuration from env vars (see `.env`)
+    pub fn init() -> Config {
+        let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+        let jwt_expires_in = env::var("JWT_EXPIRES_IN").expect("JWT_EXPIRES_IN must be set");
+        let jwt_maxage = env::var("JWT_MAXAGE").expect("JWT_MAXAGE must be set");
+
+        Config {
+            jwt_secret,
+            jwt_expires_in,
+            jwt_maxage: jwt_maxage.parse::<i64>().unwrap(),
+        }
+    }
+}
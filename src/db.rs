@@ -0,0 +1,524 @@
+use std::sync::Arc;
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use async_trait::async_trait;
+use sqlx::migrate::MigrateDatabase;
+use sqlx::mysql::{MySqlPool, MySqlPoolOptions};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+
+use crate::errors::ApiError;
+use crate::model::{ListParams, Paginated, User};
+
+// Columns `ListParams::sort_by` may select; anything else falls back to
+// `DEFAULT_SORT_COLUMN` rather than being interpolated into the query.
+const SORTABLE_COLUMNS: &[&str] = &["id", "username", "email"];
+const DEFAULT_SORT_COLUMN: &str = "id";
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+fn sort_column(sort_by: Option<&str>) -> &'static str {
+    sort_by
+        .and_then(|col| SORTABLE_COLUMNS.iter().find(|&&c| c == col))
+        .copied()
+        .unwrap_or(DEFAULT_SORT_COLUMN)
+}
+
+fn sort_direction(order: Option<&str>) -> &'static str {
+    match order.map(str::to_ascii_lowercase).as_deref() {
+        Some("desc") => "DESC",
+        _ => "ASC",
+    }
+}
+
+fn normalize_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
+
+fn normalize_offset(offset: Option<i64>) -> i64 {
+    offset.unwrap_or(0).max(0)
+}
+
+// Backend-agnostic user storage, implemented per supported database so
+// handlers can work against `Arc<dyn UserStore>` instead of a concrete pool.
+#[async_trait]
+pub trait UserStore: Send + Sync {
+    async fn list_users(&self, params: &ListParams) -> Result<Paginated<User>, ApiError>;
+
+    async fn create_user(
+        &self,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<User, ApiError>;
+
+    async fn get_user_by_id(&self, user_id: i64) -> Result<Option<User>, ApiError>;
+
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, ApiError>;
+
+    async fn delete_user(&self, user_id: i64) -> Result<Option<User>, ApiError>;
+
+    async fn verify_credentials(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<User>, ApiError>;
+}
+
+// Salt and hash a plaintext password with Argon2
+fn hash_password(password: &str) -> Result<String, ApiError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|_| ApiError::Internal("Error hashing password".to_string()))
+}
+
+// Check a plaintext password against a stored Argon2 hash in constant time
+fn verify_password(password: &str, password_hash: &str) -> bool {
+    let parsed_hash = match PasswordHash::new(password_hash) {
+        Ok(hash) => hash,
+        Err(_) => return false,
+    };
+
+    Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok()
+}
+
+// SQLite-backed store
+pub struct SqliteStore {
+    pool: SqlitePool,
+}
+
+impl SqliteStore {
+    pub async fn connect(database_url: &str) -> Result<Self, sqlx::Error> {
+        if !sqlx::sqlite::Sqlite::database_exists(database_url)
+            .await
+            .unwrap_or(false)
+        {
+            println!("Creating database {}", database_url);
+            sqlx::sqlite::Sqlite::create_database(database_url).await?;
+        }
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(10)
+            .connect(database_url)
+            .await?;
+
+        sqlx::migrate!("./migrations/sqlite").run(&pool).await?;
+
+        Ok(SqliteStore { pool })
+    }
+}
+
+#[async_trait]
+impl UserStore for SqliteStore {
+    async fn list_users(&self, params: &ListParams) -> Result<Paginated<User>, ApiError> {
+        let limit = normalize_limit(params.limit);
+        let offset = normalize_offset(params.offset);
+        let sort_by = sort_column(params.sort_by.as_deref());
+        let order = sort_direction(params.order.as_deref());
+
+        let mut conditions = Vec::new();
+        let mut binds = Vec::new();
+        if let Some(username) = &params.username {
+            conditions.push("username LIKE ?");
+            binds.push(format!("%{}%", username));
+        }
+        if let Some(email) = &params.email {
+            conditions.push("email LIKE ?");
+            binds.push(format!("%{}%", email));
+        }
+        let where_sql = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let list_sql = format!(
+            "SELECT id, username, email, password_hash FROM users {} ORDER BY {} {} LIMIT ? OFFSET ?",
+            where_sql, sort_by, order
+        );
+        let count_sql = format!("SELECT COUNT(*) FROM users {}", where_sql);
+
+        let mut list_query = sqlx::query_as::<_, User>(&list_sql);
+        let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
+        for bind in &binds {
+            list_query = list_query.bind(bind);
+            count_query = count_query.bind(bind);
+        }
+        list_query = list_query.bind(limit).bind(offset);
+
+        let items = list_query.fetch_all(&self.pool).await?;
+        let total = count_query.fetch_one(&self.pool).await?;
+
+        Ok(Paginated { items, total, limit, offset })
+    }
+
+    async fn create_user(
+        &self,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<User, ApiError> {
+        let password_hash = hash_password(password)?;
+
+        Ok(sqlx::query_as!(
+            User,
+            "INSERT INTO users (username, email, password_hash) VALUES (?, ?, ?) RETURNING id, username, email, password_hash",
+            username,
+            email,
+            password_hash
+        )
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    async fn get_user_by_id(&self, user_id: i64) -> Result<Option<User>, ApiError> {
+        Ok(sqlx::query_as!(
+            User,
+            "SELECT id, username, email, password_hash FROM users WHERE id = ?",
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, ApiError> {
+        Ok(sqlx::query_as!(
+            User,
+            "SELECT id, username, email, password_hash FROM users WHERE username = ?",
+            username
+        )
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    async fn delete_user(&self, user_id: i64) -> Result<Option<User>, ApiError> {
+        Ok(sqlx::query_as!(
+            User,
+            "DELETE FROM users WHERE id = ? RETURNING id, username, email, password_hash",
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    async fn verify_credentials(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<User>, ApiError> {
+        let user = match self.get_user_by_username(username).await? {
+            Some(user) => user,
+            None => return Ok(None),
+        };
+
+        Ok(verify_password(password, &user.password_hash).then_some(user))
+    }
+}
+
+// Postgres-backed store
+pub struct PostgresStore {
+    pool: PgPool,
+}
+
+impl PostgresStore {
+    pub async fn connect(database_url: &str) -> Result<Self, sqlx::Error> {
+        if !sqlx::postgres::Postgres::database_exists(database_url)
+            .await
+            .unwrap_or(false)
+        {
+            sqlx::postgres::Postgres::create_database(database_url).await?;
+        }
+
+        let pool = PgPoolOptions::new()
+            .max_connections(10)
+            .connect(database_url)
+            .await?;
+
+        sqlx::migrate!("./migrations/postgres").run(&pool).await?;
+
+        Ok(PostgresStore { pool })
+    }
+}
+
+#[async_trait]
+impl UserStore for PostgresStore {
+    async fn list_users(&self, params: &ListParams) -> Result<Paginated<User>, ApiError> {
+        let limit = normalize_limit(params.limit);
+        let offset = normalize_offset(params.offset);
+        let sort_by = sort_column(params.sort_by.as_deref());
+        let order = sort_direction(params.order.as_deref());
+
+        let mut conditions = Vec::new();
+        let mut binds = Vec::new();
+        let mut placeholder = 1;
+        if let Some(username) = &params.username {
+            conditions.push(format!("username LIKE ${}", placeholder));
+            binds.push(format!("%{}%", username));
+            placeholder += 1;
+        }
+        if let Some(email) = &params.email {
+            conditions.push(format!("email LIKE ${}", placeholder));
+            binds.push(format!("%{}%", email));
+            placeholder += 1;
+        }
+        let where_sql = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let list_sql = format!(
+            "SELECT id, username, email, password_hash FROM users {} ORDER BY {} {} LIMIT ${} OFFSET ${}",
+            where_sql, sort_by, order, placeholder, placeholder + 1
+        );
+        let count_sql = format!("SELECT COUNT(*) FROM users {}", where_sql);
+
+        let mut list_query = sqlx::query_as::<_, User>(&list_sql);
+        let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
+        for bind in &binds {
+            list_query = list_query.bind(bind);
+            count_query = count_query.bind(bind);
+        }
+        list_query = list_query.bind(limit).bind(offset);
+
+        let items = list_query.fetch_all(&self.pool).await?;
+        let total = count_query.fetch_one(&self.pool).await?;
+
+        Ok(Paginated { items, total, limit, offset })
+    }
+
+    async fn create_user(
+        &self,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<User, ApiError> {
+        let password_hash = hash_password(password)?;
+
+        Ok(sqlx::query_as::<_, User>(
+            "INSERT INTO users (username, email, password_hash) VALUES ($1, $2, $3) RETURNING id, username, email, password_hash",
+        )
+        .bind(username)
+        .bind(email)
+        .bind(password_hash)
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    async fn get_user_by_id(&self, user_id: i64) -> Result<Option<User>, ApiError> {
+        Ok(sqlx::query_as::<_, User>("SELECT id, username, email, password_hash FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, ApiError> {
+        Ok(sqlx::query_as::<_, User>("SELECT id, username, email, password_hash FROM users WHERE username = $1")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    async fn delete_user(&self, user_id: i64) -> Result<Option<User>, ApiError> {
+        Ok(sqlx::query_as::<_, User>("DELETE FROM users WHERE id = $1 RETURNING id, username, email, password_hash")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    async fn verify_credentials(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<User>, ApiError> {
+        let user = match self.get_user_by_username(username).await? {
+            Some(user) => user,
+            None => return Ok(None),
+        };
+
+        Ok(verify_password(password, &user.password_hash).then_some(user))
+    }
+}
+
+// MySQL-backed store
+pub struct MysqlStore {
+    pool: MySqlPool,
+}
+
+impl MysqlStore {
+    pub async fn connect(database_url: &str) -> Result<Self, sqlx::Error> {
+        if !sqlx::mysql::MySql::database_exists(database_url)
+            .await
+            .unwrap_or(false)
+        {
+            sqlx::mysql::MySql::create_database(database_url).await?;
+        }
+
+        let pool = MySqlPoolOptions::new()
+            .max_connections(10)
+            .connect(database_url)
+            .await?;
+
+        sqlx::migrate!("./migrations/mysql").run(&pool).await?;
+
+        Ok(MysqlStore { pool })
+    }
+}
+
+#[async_trait]
+impl UserStore for MysqlStore {
+    async fn list_users(&self, params: &ListParams) -> Result<Paginated<User>, ApiError> {
+        let limit = normalize_limit(params.limit);
+        let offset = normalize_offset(params.offset);
+        let sort_by = sort_column(params.sort_by.as_deref());
+        let order = sort_direction(params.order.as_deref());
+
+        let mut conditions = Vec::new();
+        let mut binds = Vec::new();
+        if let Some(username) = &params.username {
+            conditions.push("username LIKE ?");
+            binds.push(format!("%{}%", username));
+        }
+        if let Some(email) = &params.email {
+            conditions.push("email LIKE ?");
+            binds.push(format!("%{}%", email));
+        }
+        let where_sql = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let list_sql = format!(
+            "SELECT id, username, email, password_hash FROM users {} ORDER BY {} {} LIMIT ? OFFSET ?",
+            where_sql, sort_by, order
+        );
+        let count_sql = format!("SELECT COUNT(*) FROM users {}", where_sql);
+
+        let mut list_query = sqlx::query_as::<_, User>(&list_sql);
+        let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
+        for bind in &binds {
+            list_query = list_query.bind(bind);
+            count_query = count_query.bind(bind);
+        }
+        list_query = list_query.bind(limit).bind(offset);
+
+        let items = list_query.fetch_all(&self.pool).await?;
+        let total = count_query.fetch_one(&self.pool).await?;
+
+        Ok(Paginated { items, total, limit, offset })
+    }
+
+    async fn create_user(
+        &self,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<User, ApiError> {
+        let password_hash = hash_password(password)?;
+
+        sqlx::query("INSERT INTO users (username, email, password_hash) VALUES (?, ?, ?)")
+            .bind(username)
+            .bind(email)
+            .bind(&password_hash)
+            .execute(&self.pool)
+            .await?;
+
+        self.get_user_by_username(username)
+            .await?
+            .ok_or_else(|| ApiError::Internal("User not found immediately after creation".to_string()))
+    }
+
+    async fn get_user_by_id(&self, user_id: i64) -> Result<Option<User>, ApiError> {
+        Ok(sqlx::query_as::<_, User>("SELECT id, username, email, password_hash FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, ApiError> {
+        Ok(sqlx::query_as::<_, User>("SELECT id, username, email, password_hash FROM users WHERE username = ?")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    async fn delete_user(&self, user_id: i64) -> Result<Option<User>, ApiError> {
+        let user = match self.get_user_by_id(user_id).await? {
+            Some(user) => user,
+            None => return Ok(None),
+        };
+
+        // MySQL has no `DELETE ... RETURNING`, so the fetch above and this
+        // delete aren't atomic; check rows_affected rather than returning
+        // the pre-fetched user unconditionally, in case it was deleted
+        // concurrently between the two.
+        let result = sqlx::query("DELETE FROM users WHERE id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(user))
+    }
+
+    async fn verify_credentials(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<User>, ApiError> {
+        let user = match self.get_user_by_username(username).await? {
+            Some(user) => user,
+            None => return Ok(None),
+        };
+
+        Ok(verify_password(password, &user.password_hash).then_some(user))
+    }
+}
+
+// Open the SQLite pool backing the `sessions`/`roles`/`user_roles` tables.
+// These auxiliary auth tables are currently only supported against SQLite,
+// independent of which `UserStore` backend `DATABASE_URL` selects.
+pub async fn connect_auth_pool(database_url: &str) -> Result<SqlitePool, sqlx::Error> {
+    if !sqlx::sqlite::Sqlite::database_exists(database_url)
+        .await
+        .unwrap_or(false)
+    {
+        sqlx::sqlite::Sqlite::create_database(database_url).await?;
+    }
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(10)
+        .connect(database_url)
+        .await?;
+
+    sqlx::migrate!("./migrations/sqlite").run(&pool).await?;
+
+    Ok(pool)
+}
+
+// Connect to `database_url`, selecting the `UserStore` implementation that
+// matches its scheme (`sqlite:`, `postgres(ql):`, `mysql:`).
+pub async fn connect(database_url: &str) -> Result<Arc<dyn UserStore>, sqlx::Error> {
+    if database_url.starts_with("sqlite:") {
+        Ok(Arc::new(SqliteStore::connect(database_url).await?))
+    } else if database_url.starts_with("postgres:") || database_url.starts_with("postgresql:") {
+        Ok(Arc::new(PostgresStore::connect(database_url).await?))
+    } else if database_url.starts_with("mysql:") {
+        Ok(Arc::new(MysqlStore::connect(database_url).await?))
+    } else {
+        Err(sqlx::Error::Configuration(
+            format!("unsupported DATABASE_URL scheme: {}", database_url).into(),
+        ))
+    }
+}
@@ -0,0 +1,67 @@
+use actix_web::{dev::Payload, error::ErrorUnauthorized, web, Error as ActixError, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+
+use crate::{model::User, session::SessionRepository, token, AppState};
+
+// Extractor that guards a handler behind a valid `Authorization: Bearer <jwt>`
+// header, falling back to the `session_id` cookie so session-backed auth
+// works as a revocable alternative to the stateless JWT. Resolves to the
+// authenticated `User`.
+pub struct AuthenticatedUser {
+    pub user: User,
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = ActixError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let data = req
+                .app_data::<web::Data<AppState>>()
+                .cloned()
+                .ok_or_else(|| ErrorUnauthorized("Missing application state"))?;
+
+            let bearer_token = req
+                .headers()
+                .get("Authorization")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "));
+
+            let user = if let Some(token) = bearer_token {
+                let claims = token::decode_token(token, &data.config.jwt_secret)
+                    .map_err(|_| ErrorUnauthorized("Invalid token or token has expired"))?;
+
+                let user_id: i64 = claims
+                    .sub
+                    .parse()
+                    .map_err(|_| ErrorUnauthorized("Invalid token subject"))?;
+
+                data.db
+                    .get_user_by_id(user_id)
+                    .await
+                    .map_err(|_| ErrorUnauthorized("Error fetching user"))?
+                    .ok_or_else(|| ErrorUnauthorized("The user belonging to this token no longer exists"))?
+            } else {
+                let session_id = req
+                    .cookie("session_id")
+                    .ok_or_else(|| ErrorUnauthorized("You are not logged in, please provide a token"))?;
+
+                let user_id = SessionRepository::lookup(&data.auth_db, session_id.value())
+                    .await
+                    .map_err(|_| ErrorUnauthorized("Error fetching session"))?
+                    .ok_or_else(|| ErrorUnauthorized("Session is invalid or has expired"))?;
+
+                data.db
+                    .get_user_by_id(user_id)
+                    .await
+                    .map_err(|_| ErrorUnauthorized("Error fetching user"))?
+                    .ok_or_else(|| ErrorUnauthorized("The user belonging to this session no longer exists"))?
+            };
+
+            Ok(AuthenticatedUser { user })
+        })
+    }
+}
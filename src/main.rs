@@ -1,46 +1,40 @@
 use actix_web::{
-    web, 
-    App, 
-    HttpServer, 
-    Responder, 
+    cookie::Cookie,
+    web,
+    App,
+    HttpServer,
     HttpResponse,
     middleware::Logger
 };
 
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::sqlite::SqlitePool;
 use std::env;
 use std::sync::Arc;
-use sqlx::migrate::MigrateDatabase;
-use serde::{Serialize, Deserialize};
 
-// Database connection and migration helper
-pub struct DatabaseConnection {
-    pub pool: SqlitePool,
-}
-
-impl DatabaseConnection {
-    // Initialize database connection and run migrations
-    pub async fn new(database_url: &str) -> Result<Self, sqlx::Error> {
-        // Create database pool
-        let pool = SqlitePoolOptions::new()
-            .max_connections(10)
-            .connect(database_url)
-            .await?;
-
-        // Run migrations
-        sqlx::migrate!("./migrations")
-            .run(&pool)
-            .await?;
-        Ok(DatabaseConnection { pool })
-    }
-}
-
-
-
-// Application state with database connection
+mod config;
+mod db;
+mod errors;
+mod jwt_auth;
+mod model;
+mod rbac;
+mod session;
+mod token;
+
+use config::Config;
+use db::UserStore;
+use errors::ApiError;
+use jwt_auth::AuthenticatedUser;
+use model::{ListParams, LoginResponse, LoginUserSchema, RegisterUserSchema};
+use rbac::{DeleteUserPermission, RequirePermission, WriteUserPermission};
+use session::SessionRepository;
+
+// Application state with a backend-agnostic user store, session store, and
+// auth config
 #[derive(Clone)]
 pub struct AppState {
-    pub db: Arc<SqlitePool>,
+    pub db: Arc<dyn UserStore>,
+    pub auth_db: SqlitePool,
+    pub config: Config,
 }
 
 // Main application setup function
@@ -49,23 +43,20 @@ pub async fn setup_database() -> Result<AppState, sqlx::Error> {
     let database_url = env::var("DATABASE_URL")
         .unwrap_or_else(|_| "sqlite:users.db".to_string());
 
-    // Ensure database exists and is migrated
-    if !sqlx::sqlite::Sqlite::database_exists(&database_url).await
-        .unwrap_or(false) 
-    {
-        println!("Creating database {}", database_url);
-        sqlx::sqlite::Sqlite::create_database(&database_url).await?;
-    }
+    // Connect to whichever backend the DATABASE_URL scheme selects
+    let db = db::connect(&database_url).await?;
 
-    // Initialize database connection
-    let db_conn = DatabaseConnection::new(&database_url).await?;
-
-    // Wrap pool in Arc for thread-safe sharing
-    let app_state = AppState {
-        db: Arc::new(db_conn.pool),
-    };
+    // Sessions and RBAC tables live in a separate SQLite pool opened from
+    // `AUTH_DATABASE_URL`, which defaults to `DATABASE_URL`; that URL must be
+    // a `sqlite:` one
+    let auth_database_url = env::var("AUTH_DATABASE_URL").unwrap_or(database_url);
+    let auth_db = db::connect_auth_pool(&auth_database_url).await?;
 
-    Ok(app_state)
+    Ok(AppState {
+        db,
+        auth_db,
+        config: Config::init(),
+    })
 }
 
 // Example main function integrating database setup
@@ -91,6 +82,8 @@ async fn main() -> std::io::Result<()> {
                 web::scope("/users")
                     .route("", web::get().to(list_users))
                     .route("", web::post().to(create_user))
+                    .route("/login", web::post().to(login))
+                    .route("/logout", web::post().to(logout))
                     .route("/{id}", web::get().to(get_user))
                     .route("/{id}", web::delete().to(delete_user))
             )
@@ -98,128 +91,133 @@ async fn main() -> std::io::Result<()> {
     .bind("0.0.0.0:8080")?
     .run()
     .await
-    
+
 }
 
-// Example repository pattern for database operations
-pub struct UserRepository;
-
-impl UserRepository {
-    // List all users
-    pub async fn list_users(pool: &SqlitePool) -> Result<Vec<User>, sqlx::Error> {
-        sqlx::query_as!(
-            User, 
-            "SELECT id, username, email FROM users"
-        )
-        .fetch_all(pool)
-        .await
-    }
+// Route Handlers
+// List Users Handler - supports pagination, a sortable-column whitelist, and
+// substring filtering on `username`/`email` via `ListParams`
+async fn list_users(
+    data: web::Data<AppState>,
+    params: web::Query<ListParams>
+) -> Result<HttpResponse, ApiError> {
+    let page = data.db.list_users(&params).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
 
-    // Create a new user
-    pub async fn create_user(
-        pool: &SqlitePool, 
-        username: &str, 
-        email: &str
-    ) -> Result<User, sqlx::Error> {
-        sqlx::query_as!(
-            User,
-            "INSERT INTO users (username, email) VALUES (?, ?) RETURNING id, username, email",
-            username,
-            email
-        )
-        .fetch_one(pool)
+// Create User Handler - open only to callers holding `WriteUserPermission`,
+// except for the very first user ever created: since nothing can grant a
+// role before an admin exists, that bootstrap call is let through
+// unauthenticated and the resulting user is made admin. `claim_bootstrap_admin`
+// is an atomic, single-row insert, so at most one concurrent request can ever
+// win it, even if several race in against an empty `users` table. Every
+// other created user is granted the baseline `user` role, since nothing
+// else ever populates `user_roles`.
+async fn create_user(
+    data: web::Data<AppState>,
+    user: web::Json<RegisterUserSchema>,
+    guard: Option<RequirePermission<WriteUserPermission>>
+) -> Result<HttpResponse, ApiError> {
+    let is_bootstrap = rbac::claim_bootstrap_admin(&data.auth_db)
         .await
-    }
+        .map_err(|_| ApiError::Internal("Error checking bootstrap status".to_string()))?;
 
-    // Get user by ID
-    pub async fn get_user_by_id(
-        pool: &SqlitePool, 
-        user_id: i64
-    ) -> Result<Option<User>, sqlx::Error> {
-        sqlx::query_as!(
-            User,
-            "SELECT id, username, email FROM users WHERE id = ?",
-            user_id
-        )
-        .fetch_optional(pool)
-        .await
+    if !is_bootstrap && guard.is_none() {
+        return Err(ApiError::Forbidden("You do not have permission to perform this action".to_string()));
     }
 
-    // Delete user by ID
-    pub async fn delete_user(
-        pool: &SqlitePool, 
-        user_id: i64
-    ) -> Result<Option<User>, sqlx::Error> {
-        sqlx::query_as!(
-            User,
-            "DELETE FROM users WHERE id = ? RETURNING id, username, email",
-            user_id
-        )
-        .fetch_optional(pool)
+    let created_user = data.db.create_user(&user.username, &user.email, &user.password).await?;
+    let created_id = created_user
+        .id
+        .ok_or_else(|| ApiError::Internal("User has no id".to_string()))?;
+
+    let role = if is_bootstrap { "admin" } else { "user" };
+    rbac::assign_role(&data.auth_db, created_id, role)
         .await
-    }
+        .map_err(|_| ApiError::Internal("Error assigning role".to_string()))?;
+
+    Ok(HttpResponse::Created().json(created_user))
 }
 
+// Login Handler - verifies credentials, issues a signed JWT, and starts a
+// server-side session as a revocable alternative to the JWT
+async fn login(
+    data: web::Data<AppState>,
+    body: web::Json<LoginUserSchema>
+) -> Result<HttpResponse, ApiError> {
+    let user = data
+        .db
+        .verify_credentials(&body.username, &body.password)
+        .await?
+        .ok_or_else(|| ApiError::Unauthorized("Invalid username or password".to_string()))?;
+
+    let user_id = user
+        .id
+        .ok_or_else(|| ApiError::Internal("User has no id".to_string()))?;
+
+    let token = token::create_token(&user_id.to_string(), &data.config.jwt_secret, data.config.jwt_maxage)
+        .map_err(|_| ApiError::Internal("Error creating token".to_string()))?;
+
+    let session = SessionRepository::create(&data.auth_db, user_id)
+        .await
+        .map_err(|_| ApiError::Internal("Error creating session".to_string()))?;
 
+    let session_cookie = Cookie::build("session_id", session.id)
+        .http_only(true)
+        .secure(true)
+        .finish();
 
-// Route Handlers
-// List Users Handler
-async fn list_users(data: web::Data<AppState>) -> impl Responder {
-    match UserRepository::list_users(&data.db).await {
-        Ok(users) => HttpResponse::Ok().json(users),
-        Err(_) => HttpResponse::InternalServerError().body("Error fetching users"),
-    }
+    Ok(HttpResponse::Ok()
+        .cookie(session_cookie)
+        .json(LoginResponse { token }))
 }
 
-// Create User Handler
-async fn create_user(
-    data: web::Data<AppState>, 
-    user: web::Json<User>
-) -> impl Responder {
-    match UserRepository::create_user(
-        &data.db, 
-        &user.username, 
-        &user.email
-    ).await {
-        Ok(created_user) => HttpResponse::Created().json(created_user),
-        Err(_) => HttpResponse::InternalServerError().body("Error creating user"),
-    }
+// Logout Handler - revokes the session named by the `session_id` cookie
+async fn logout(data: web::Data<AppState>, req: actix_web::HttpRequest) -> Result<HttpResponse, ApiError> {
+    let session_id = match req.cookie("session_id") {
+        Some(cookie) => cookie.value().to_string(),
+        None => return Ok(HttpResponse::Ok().finish()),
+    };
+
+    SessionRepository::destroy(&data.auth_db, &session_id)
+        .await
+        .map_err(|_| ApiError::Internal("Error destroying session".to_string()))?;
+
+    let mut removal = Cookie::build("session_id", "").http_only(true).secure(true).finish();
+    removal.make_removal();
+    Ok(HttpResponse::Ok().cookie(removal).finish())
 }
 
 // Get User Handler
 async fn get_user(
-    data: web::Data<AppState>, 
-    path: web::Path<i64>
-) -> impl Responder {
+    data: web::Data<AppState>,
+    path: web::Path<i64>,
+    _auth: AuthenticatedUser
+) -> Result<HttpResponse, ApiError> {
     let user_id = path.into_inner();
-    
-    match UserRepository::get_user_by_id(&data.db, user_id).await {
-        Ok(Some(user)) => HttpResponse::Ok().json(user),
-        Ok(None) => HttpResponse::NotFound().body("User not found"),
-        Err(_) => HttpResponse::InternalServerError().body("Error fetching user"),
-    }
+
+    let user = data
+        .db
+        .get_user_by_id(user_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(user))
 }
 
 // Delete User Handler
 async fn delete_user(
-    data: web::Data<AppState>, 
-    path: web::Path<i64>
-) -> impl Responder {
+    data: web::Data<AppState>,
+    path: web::Path<i64>,
+    _guard: RequirePermission<DeleteUserPermission>
+) -> Result<HttpResponse, ApiError> {
     let user_id = path.into_inner();
-    
-    match UserRepository::delete_user(&data.db, user_id).await {
-        Ok(Some(deleted_user)) => HttpResponse::Ok().json(deleted_user),
-        Ok(None) => HttpResponse::NotFound().body("User not found"),
-        Err(_) => HttpResponse::InternalServerError().body("Error deleting user"),
-    }
-}
 
+    let deleted_user = data
+        .db
+        .delete_user(user_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
 
-// User Model
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-pub struct User {
-    pub id: Option<i64>,
-    pub username: String,
-    pub email: String,
+    Ok(HttpResponse::Ok().json(deleted_user))
 }
-
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+// User Model
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct User {
+    pub id: Option<i64>,
+    pub username: String,
+    pub email: String,
+    // Never serialized back to clients; only populated when reading from the DB
+    #[serde(skip_serializing, default)]
+    pub password_hash: String,
+}
+
+// Payload for `POST /users` when registering a new account
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterUserSchema {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+}
+
+// Payload for `POST /users/login`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginUserSchema {
+    pub username: String,
+    pub password: String,
+}
+
+// Query string accepted by `GET /users`
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub sort_by: Option<String>,
+    pub order: Option<String>,
+    pub username: Option<String>,
+    pub email: Option<String>,
+}
+
+// Paginated response envelope for `GET /users`
+#[derive(Debug, Serialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+// Response body for a successful login
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginResponse {
+    pub token: String,
+}
@@ -0,0 +1,138 @@
+use std::marker::PhantomData;
+
+use actix_web::{dev::Payload, error::ErrorForbidden, web, Error as ActixError, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use sqlx::sqlite::SqlitePool;
+
+use crate::{jwt_auth::AuthenticatedUser, model::User, AppState};
+
+// A permission that can be required of an authenticated user before a
+// handler runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    ReadUser,
+    WriteUser,
+    DeleteUser,
+}
+
+impl Permission {
+    // Roles (as stored in `roles.name`) that carry this permission
+    fn granting_roles(self) -> &'static [&'static str] {
+        match self {
+            Permission::ReadUser => &["admin", "user"],
+            Permission::WriteUser => &["admin"],
+            Permission::DeleteUser => &["admin"],
+        }
+    }
+}
+
+// Check whether `user_id` holds any role that grants `permission`
+pub async fn user_has_permission(
+    pool: &SqlitePool,
+    user_id: i64,
+    permission: Permission,
+) -> Result<bool, sqlx::Error> {
+    let roles: Vec<String> = sqlx::query_scalar!(
+        "SELECT roles.name FROM user_roles JOIN roles ON roles.id = user_roles.role_id WHERE user_roles.user_id = ?",
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(roles
+        .iter()
+        .any(|role| permission.granting_roles().contains(&role.as_str())))
+}
+
+// Atomically claim the one-time bootstrap-admin grant by inserting the
+// single allowed row in `bootstrap_admin_claim`. Returns `true` for exactly
+// one caller, ever, even under concurrent requests: SQLite enforces the
+// `PRIMARY KEY` uniqueness at the database level, so only the first insert
+// succeeds and every other one fails with a unique-constraint violation.
+pub async fn claim_bootstrap_admin(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+    match sqlx::query!("INSERT INTO bootstrap_admin_claim (id) VALUES (1)")
+        .execute(pool)
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+// Grant `user_id` the named role (as stored in `roles.name`), ignoring the
+// call if they already hold it. This is the only path that ever populates
+// `user_roles`; callers decide which role a newly created user gets.
+pub async fn assign_role(pool: &SqlitePool, user_id: i64, role_name: &str) -> Result<(), sqlx::Error> {
+    let role_id: i64 = sqlx::query_scalar!("SELECT id FROM roles WHERE name = ?", role_name)
+        .fetch_one(pool)
+        .await?;
+
+    sqlx::query!(
+        "INSERT OR IGNORE INTO user_roles (user_id, role_id) VALUES (?, ?)",
+        user_id,
+        role_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Associates a marker type with the `Permission` it requires, letting
+// `RequirePermission<P>` be instantiated per-permission as a route guard.
+pub trait RequiredPermission {
+    const PERMISSION: Permission;
+}
+
+pub struct WriteUserPermission;
+impl RequiredPermission for WriteUserPermission {
+    const PERMISSION: Permission = Permission::WriteUser;
+}
+
+pub struct DeleteUserPermission;
+impl RequiredPermission for DeleteUserPermission {
+    const PERMISSION: Permission = Permission::DeleteUser;
+}
+
+// Extractor that guards a handler behind an authenticated user who holds
+// `P`'s permission, rejecting with 403 Forbidden otherwise.
+pub struct RequirePermission<P: RequiredPermission> {
+    pub user: User,
+    _permission: PhantomData<P>,
+}
+
+impl<P: RequiredPermission + 'static> FromRequest for RequirePermission<P> {
+    type Error = ActixError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let authenticated = AuthenticatedUser::from_request(req, payload);
+        let data = req.app_data::<web::Data<AppState>>().cloned();
+
+        Box::pin(async move {
+            let data = data.ok_or_else(|| ErrorForbidden("Missing application state"))?;
+            let authenticated = authenticated.await.map_err(|_| {
+                ErrorForbidden("You are not logged in, please provide a token")
+            })?;
+
+            let user_id = authenticated
+                .user
+                .id
+                .ok_or_else(|| ErrorForbidden("User has no id"))?;
+
+            let allowed = user_has_permission(&data.auth_db, user_id, P::PERMISSION)
+                .await
+                .map_err(|_| ErrorForbidden("Error checking permissions"))?;
+
+            if !allowed {
+                return Err(ErrorForbidden("You do not have permission to perform this action"));
+            }
+
+            Ok(RequirePermission {
+                user: authenticated.user,
+                _permission: PhantomData,
+            })
+        })
+    }
+}
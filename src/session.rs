@@ -0,0 +1,57 @@
+use chrono::{Duration, Utc};
+use sqlx::sqlite::SqlitePool;
+use uuid::Uuid;
+
+// A server-side session, used as a revocable alternative to stateless JWTs
+pub struct Session {
+    pub id: String,
+    pub user_id: i64,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+pub struct SessionRepository;
+
+impl SessionRepository {
+    // Start a new session for `user_id`, valid for 7 days
+    pub async fn create(pool: &SqlitePool, user_id: i64) -> Result<Session, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let created_at = Utc::now().to_rfc3339();
+        let expires_at = (Utc::now() + Duration::days(7)).to_rfc3339();
+
+        sqlx::query_as!(
+            Session,
+            "INSERT INTO sessions (id, user_id, created_at, expires_at) VALUES (?, ?, ?, ?) RETURNING id, user_id, created_at, expires_at",
+            id,
+            user_id,
+            created_at,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    // Resolve a session id to the user id it belongs to, ignoring expired
+    // sessions. Returns only the id rather than joining against a local
+    // `users` table: the real `users` table may live in a different
+    // database entirely (the `UserStore` backend selected by
+    // `DATABASE_URL`), so the caller resolves the id through `AppState.db`.
+    pub async fn lookup(pool: &SqlitePool, session_id: &str) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar!(
+            "SELECT user_id FROM sessions WHERE id = ? AND expires_at > ?",
+            session_id,
+            Utc::now().to_rfc3339()
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    // Revoke a session, logging it out immediately wherever it's used
+    pub async fn destroy(pool: &SqlitePool, session_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM sessions WHERE id = ?", session_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+// Claims encoded in the JWT: `sub` is the user id, `exp`/`iat` are unix timestamps
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub sub: String,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+// Sign a JWT for `user_id`, valid for `max_age` minutes
+pub fn create_token(
+    user_id: &str,
+    secret: &str,
+    max_age: i64,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let iat = now.timestamp() as usize;
+    let exp = (now + Duration::minutes(max_age)).timestamp() as usize;
+
+    let claims = TokenClaims {
+        sub: user_id.to_string(),
+        iat,
+        exp,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+}
+
+// Validate a JWT's signature and expiry, returning the claims on success
+pub fn decode_token(
+    token: &str,
+    secret: &str,
+) -> Result<TokenClaims, jsonwebtoken::errors::Error> {
+    let claims = decode::<TokenClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )?
+    .claims;
+
+    Ok(claims)
+}